@@ -1,5 +1,8 @@
+use std::collections::HashSet;
+
 use revm::{
     interpreter::{InstructionResult, Interpreter},
+    primitives::{Address, B256, U256},
     Database, EVMData, Inspector, JournaledState,
 };
 use tokio::{
@@ -14,6 +17,14 @@ pub enum DebugCommand {
     StepBackwards,
     /// Steps forwards
     StepForwards,
+    /// Runs freely until the next breakpoint is hit, or execution ends.
+    RunToBreakpoint,
+    /// Registers a breakpoint at the given program counter.
+    SetBreakpoint(usize),
+    /// Removes the breakpoint at the given program counter, if any.
+    ClearBreakpoint(usize),
+    /// Resumes free-running execution, equivalent to [`DebugCommand::RunToBreakpoint`].
+    Continue,
     /// Stops the EVM
     Stop,
 }
@@ -34,8 +45,24 @@ pub struct StepState {
     journaled_state: JournaledState,
 }
 
-#[derive(Clone)]
-pub struct StepInfo {}
+/// A snapshot of the interpreter's machine state at a single step.
+#[derive(Clone, Debug)]
+pub struct StepInfo {
+    /// The program counter, i.e. the offset into the executing contract's bytecode.
+    pub program_counter: usize,
+    /// The opcode about to be executed.
+    pub opcode: u8,
+    /// The remaining gas.
+    pub gas_remaining: u64,
+    /// A snapshot of the interpreter's stack.
+    pub stack: Vec<U256>,
+    /// A snapshot of the interpreter's memory.
+    pub memory: Vec<u8>,
+    /// The address of the currently executing contract.
+    pub contract_address: Address,
+    /// The hash of the currently executing contract's code.
+    pub code_hash: B256,
+}
 
 /// A debugger of EVM bytecode.
 pub struct AssemblyDebugger {
@@ -46,6 +73,11 @@ pub struct AssemblyDebugger {
     command_receiver: mpsc::UnboundedReceiver<DebugCommand>,
     step_info_sender: broadcast::Sender<StepInfo>,
     error: Option<DebugError>,
+    /// Program counters at which execution should pause.
+    breakpoints: HashSet<usize>,
+    /// Whether the debugger is currently running freely, only pausing at a
+    /// breakpoint, rather than blocking on every single step.
+    running: bool,
 }
 
 impl AssemblyDebugger {
@@ -59,6 +91,8 @@ impl AssemblyDebugger {
             command_receiver,
             step_info_sender: broadcast::channel(capacity).0,
             error: None,
+            breakpoints: HashSet::new(),
+            running: false,
         }
     }
 
@@ -71,6 +105,46 @@ impl AssemblyDebugger {
     pub fn subscribe_commander(&self) -> mpsc::UnboundedSender<DebugCommand> {
         self.command_sender.clone()
     }
+
+    /// Applies a [`DebugCommand`], returning `Some` if the EVM should stop immediately.
+    fn handle_command<DB: Database>(
+        &mut self,
+        command: DebugCommand,
+        interp: &mut Interpreter,
+        data: &mut EVMData<'_, DB>,
+    ) -> Option<InstructionResult> {
+        match command {
+            DebugCommand::StepBackwards => {
+                if let Some(step) = self.step_history.pop() {
+                    interp.instruction_pointer = step.instruction_pointer;
+                    // TODO: other interp member variables
+
+                    data.journaled_state = step.journaled_state;
+                    data.error = None;
+                }
+                self.running = false;
+            }
+            DebugCommand::StepForwards => {
+                self.pre_step_instruction_pointer = Some(interp.instruction_pointer);
+                self.running = false;
+            }
+            DebugCommand::RunToBreakpoint | DebugCommand::Continue => {
+                self.running = true;
+            }
+            DebugCommand::SetBreakpoint(pc) => {
+                self.breakpoints.insert(pc);
+            }
+            DebugCommand::ClearBreakpoint(pc) => {
+                self.breakpoints.remove(&pc);
+            }
+            DebugCommand::Stop => {
+                self.error = Some(DebugError::ExecutionStopped);
+                return Some(InstructionResult::FatalExternalError);
+            }
+        }
+
+        None
+    }
 }
 
 impl<DB: Database> Inspector<DB> for AssemblyDebugger {
@@ -80,30 +154,45 @@ impl<DB: Database> Inspector<DB> for AssemblyDebugger {
         data: &mut EVMData<'_, DB>,
         _is_static: bool,
     ) -> InstructionResult {
+        let program_counter = interp.program_counter();
+
+        let step_info = StepInfo {
+            program_counter,
+            opcode: interp.current_opcode(),
+            gas_remaining: interp.gas.remaining(),
+            stack: interp.stack.data().clone(),
+            memory: interp.memory.data().clone(),
+            contract_address: interp.contract.address,
+            code_hash: interp.contract.hash,
+        };
+
         // We don't care whether someone is listening, so don't handle the error for when there are no listeners
-        let _ = self.step_info_sender.send(StepInfo {});
+        let _ = self.step_info_sender.send(step_info);
+
+        if self.breakpoints.contains(&program_counter) {
+            self.running = false;
+        }
 
         // TODO: split between Database & Inspector errors when using `FatalExternalError`
 
-        if let Some(command) = task::block_in_place(|| self.command_receiver.blocking_recv()) {
-            match command {
-                DebugCommand::StepBackwards => {
-                    if let Some(step) = self.step_history.pop() {
-                        interp.instruction_pointer = step.instruction_pointer;
-                        // TODO: other interp member variables
-
-                        data.journaled_state = step.journaled_state;
-                        data.error = None;
-                    }
-                }
-                DebugCommand::StepForwards => {
-                    self.pre_step_instruction_pointer = Some(interp.instruction_pointer);
-                }
-                DebugCommand::Stop => {
-                    self.error = Some(DebugError::ExecutionStopped);
-                    return InstructionResult::FatalExternalError;
+        if self.running {
+            // Apply any commands queued up while running freely (e.g. a new
+            // breakpoint or a stop request) without blocking execution.
+            while let Ok(command) = self.command_receiver.try_recv() {
+                if let Some(result) = self.handle_command(command, interp, data) {
+                    return result;
                 }
             }
+
+            if self.running {
+                return InstructionResult::Continue;
+            }
+        }
+
+        if let Some(command) = task::block_in_place(|| self.command_receiver.blocking_recv()) {
+            if let Some(result) = self.handle_command(command, interp, data) {
+                return result;
+            }
         } else {
             self.error = Some(DebugError::CommandChannelClosed);
             return InstructionResult::FatalExternalError;
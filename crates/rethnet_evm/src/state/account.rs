@@ -1,10 +1,12 @@
-use rethnet_eth::{account::KECCAK_EMPTY, state::Storage};
-use revm::primitives::{AccountInfo, Bytecode};
+use rethnet_eth::{account::KECCAK_EMPTY, state::Storage, B256};
+use revm::primitives::{AccountInfo, Bytecode, U256};
 
 #[derive(Clone, Debug, Default)]
 pub struct RethnetAccount {
     pub info: AccountInfo,
     pub storage: Storage,
+    /// Memoized root of `storage`, recomputed lazily after it is dirtied.
+    storage_root: Option<B256>,
 }
 
 impl RethnetAccount {
@@ -21,6 +23,65 @@ impl RethnetAccount {
 
         None
     }
+
+    /// Returns the root of the account's storage, recomputing it only if
+    /// `storage` was mutated since the last call.
+    pub fn storage_root(&mut self) -> B256 {
+        if let Some(storage_root) = self.storage_root {
+            return storage_root;
+        }
+
+        let storage_root = rethnet_eth::state::storage_root(&self.storage);
+        self.storage_root = Some(storage_root);
+        storage_root
+    }
+
+    /// Marks the memoized storage root as stale. Must be called whenever
+    /// `storage` is mutated directly.
+    pub fn dirty_storage_root(&mut self) {
+        self.storage_root = None;
+    }
+
+    /// Returns how this account's code is currently represented.
+    pub fn code_state(&self) -> CodeState {
+        if self.info.code_hash == KECCAK_EMPTY {
+            CodeState::Empty
+        } else if self.info.code.is_some() {
+            CodeState::Inline
+        } else {
+            CodeState::ByHash
+        }
+    }
+}
+
+/// Discriminates how an account's code is represented, so e.g. an
+/// empty-code account doesn't need to carry a redundant code hash when
+/// materialized.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CodeState {
+    /// The account has no code.
+    Empty,
+    /// The code is still inlined in the account, i.e. it hasn't been split
+    /// out into the contract store yet.
+    Inline,
+    /// The code has been split out into the contract store and is only
+    /// referenced here by its hash.
+    ByHash,
+}
+
+/// A full materialization of a single account's state, with its code
+/// inlined and its storage fully expanded. Used for debugging and test
+/// assertions (see [`super::StateDebug::dump`]).
+#[derive(Clone, Debug)]
+pub struct AccountDump {
+    /// The account's balance.
+    pub balance: U256,
+    /// The account's nonce.
+    pub nonce: u64,
+    /// The account's code, inlined.
+    pub code: Bytecode,
+    /// The account's full, expanded storage map.
+    pub storage: Storage,
 }
 
 impl From<AccountInfo> for RethnetAccount {
@@ -28,6 +89,7 @@ impl From<AccountInfo> for RethnetAccount {
         Self {
             info,
             storage: Storage::default(),
+            storage_root: None,
         }
     }
 }
@@ -1,16 +1,311 @@
-use hashbrown::HashMap;
-use rethnet_eth::{
-    account::BasicAccount,
-    state::{state_root, storage_root},
-    Address, B256, U256,
-};
+use std::sync::{Arc, Mutex, OnceLock};
+
+use hashbrown::{HashMap, HashSet};
+use rethnet_eth::{account::BasicAccount, state::state_root, Address, B256, U256};
 use revm::{
     db::State,
     primitives::{Account, AccountInfo, Bytecode, KECCAK_EMPTY},
     DatabaseCommit,
 };
 
-use super::{account::RethnetAccount, StateDebug, StateError};
+use super::{
+    account::{CodeState, RethnetAccount},
+    AccountChange, AccountDump, Change, StateDebug, StateDiff, StateError,
+};
+
+/// A crate-global, content-addressed contract store shared by every layer,
+/// account and snapshot, so identical bytecode is stored exactly once no
+/// matter how many of them reference it.
+static CONTRACTS: OnceLock<Mutex<HashMap<B256, Bytecode>>> = OnceLock::new();
+
+fn contracts() -> &'static Mutex<HashMap<B256, Bytecode>> {
+    CONTRACTS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Inserts `code` into the global contract store, keyed by its hash.
+fn insert_contract(code: Bytecode) {
+    contracts()
+        .lock()
+        .unwrap()
+        .entry(code.hash())
+        .or_insert(code);
+}
+
+/// Materializes a single account, resolving its code (inlined or split out
+/// into the global contract store) and expanding its storage.
+fn account_dump(account: &RethnetAccount) -> AccountDump {
+    let code = match account.code_state() {
+        CodeState::Empty => Bytecode::new(),
+        CodeState::Inline => account.info.code.clone().unwrap(),
+        CodeState::ByHash => contracts()
+            .lock()
+            .unwrap()
+            .get(&account.info.code_hash)
+            .cloned()
+            .unwrap_or_else(Bytecode::new),
+    };
+
+    AccountDump {
+        balance: account.info.balance,
+        nonce: account.info.nonce,
+        code,
+        storage: account.storage.clone(),
+    }
+}
+
+/// Collapses a layer stack into a full account dump, reusing the same
+/// top-to-bottom traversal as [`LayeredState::account`], where the topmost
+/// layer holding an entry for an address wins.
+fn dump_layers(stack: &[RethnetLayer]) -> HashMap<Address, AccountDump> {
+    let mut accounts: HashMap<Address, Option<AccountDump>> = HashMap::new();
+
+    stack
+        .iter()
+        .rev()
+        .flat_map(|layer| layer.accounts.iter())
+        .for_each(|(address, account)| {
+            accounts
+                .entry(*address)
+                .or_insert_with(|| account.as_ref().map(account_dump));
+        });
+
+    accounts
+        .into_iter()
+        .filter_map(|(address, account)| account.map(|account| (address, account)))
+        .collect()
+}
+
+/// Diffs two dumps of the same account, which is known to exist in both states.
+fn diff_accounts(before: &AccountDump, after: &AccountDump) -> AccountChange {
+    let balance = (before.balance != after.balance).then(|| Change {
+        before: before.balance,
+        after: after.balance,
+    });
+
+    let nonce = (before.nonce != after.nonce).then(|| Change {
+        before: before.nonce,
+        after: after.nonce,
+    });
+
+    let code = (before.code.hash() != after.code.hash()).then(|| Change {
+        before: before.code.clone(),
+        after: after.code.clone(),
+    });
+
+    let mut storage = HashMap::new();
+
+    before.storage.iter().for_each(|(index, before_value)| {
+        let after_value = after.storage.get(index).copied().unwrap_or(U256::ZERO);
+        if *before_value != after_value {
+            storage.insert(
+                *index,
+                Change {
+                    before: *before_value,
+                    after: after_value,
+                },
+            );
+        }
+    });
+
+    after.storage.iter().for_each(|(index, after_value)| {
+        if !before.storage.contains_key(index) && *after_value != U256::ZERO {
+            storage.insert(
+                *index,
+                Change {
+                    before: U256::ZERO,
+                    after: *after_value,
+                },
+            );
+        }
+    });
+
+    AccountChange::Changed {
+        balance,
+        nonce,
+        code,
+        storage,
+    }
+}
+
+/// The default number of addresses kept in the canonical state cache.
+const DEFAULT_CACHE_CAPACITY: usize = 1_000;
+
+/// An intrusive doubly-linked list tracking LRU order for [`StateCache`],
+/// so touching an address is an `O(1)` re-link rather than a scan for its
+/// position.
+#[derive(Clone, Debug, Default)]
+struct LruList {
+    /// Maps each tracked address to its (more-recently-used, less-recently-used) neighbours.
+    links: HashMap<Address, (Option<Address>, Option<Address>)>,
+    /// The most-recently-used address.
+    head: Option<Address>,
+    /// The least-recently-used address.
+    tail: Option<Address>,
+}
+
+impl LruList {
+    fn len(&self) -> usize {
+        self.links.len()
+    }
+
+    /// Unlinks `address` from the list, if it's tracked, without removing
+    /// the other addresses' knowledge of it until their neighbours are
+    /// patched.
+    fn unlink(&mut self, address: &Address) {
+        let Some((prev, next)) = self.links.remove(address) else {
+            return;
+        };
+
+        match prev {
+            Some(prev) => self.links.get_mut(&prev).unwrap().1 = next,
+            None => self.head = next,
+        }
+        match next {
+            Some(next) => self.links.get_mut(&next).unwrap().0 = prev,
+            None => self.tail = prev,
+        }
+    }
+
+    /// Moves `address` to the most-recently-used position, tracking it if
+    /// it wasn't already.
+    fn touch(&mut self, address: Address) {
+        self.unlink(&address);
+
+        let old_head = self.head.replace(address);
+        match old_head {
+            Some(old_head) => self.links.get_mut(&old_head).unwrap().0 = Some(address),
+            None => self.tail = Some(address),
+        }
+
+        self.links.insert(address, (None, old_head));
+    }
+
+    /// Removes and returns the least-recently-used address, if any.
+    fn pop_lru(&mut self) -> Option<Address> {
+        let address = self.tail?;
+        self.unlink(&address);
+        Some(address)
+    }
+
+    fn remove(&mut self, address: &Address) {
+        self.unlink(address);
+    }
+
+    fn clear(&mut self) {
+        self.links.clear();
+        self.head = None;
+        self.tail = None;
+    }
+}
+
+/// A canonical, read-through cache over the layer stack, indexed by address.
+/// Populated lazily on first lookup and bounded by an LRU eviction policy, so
+/// repeated reads of hot accounts/storage slots don't have to walk every
+/// layer in the checkpoint stack.
+#[derive(Clone, Debug)]
+struct StateCache {
+    accounts: HashMap<Address, AccountInfo>,
+    storage: HashMap<(Address, U256), U256>,
+    /// Tracks recency for LRU eviction in `O(1)` per touch.
+    recency: LruList,
+    /// Addresses touched since each open `checkpoint`, one generation per
+    /// nested checkpoint (innermost last), so a `revert` can drop just the
+    /// entries touched by the checkpoint it's reverting instead of either
+    /// clearing the whole cache or losing track of outer generations.
+    touched_per_checkpoint: Vec<HashSet<Address>>,
+    capacity: usize,
+}
+
+impl StateCache {
+    fn with_capacity(capacity: usize) -> Self {
+        Self {
+            accounts: HashMap::new(),
+            storage: HashMap::new(),
+            recency: LruList::default(),
+            touched_per_checkpoint: vec![HashSet::new()],
+            capacity,
+        }
+    }
+
+    fn touch(&mut self, address: Address) {
+        self.recency.touch(address);
+
+        while self.recency.len() > self.capacity {
+            if let Some(evicted) = self.recency.pop_lru() {
+                self.invalidate_account(&evicted);
+            }
+        }
+    }
+
+    fn account(&mut self, address: &Address) -> Option<AccountInfo> {
+        let account = self.accounts.get(address).cloned();
+        if account.is_some() {
+            self.touch(*address);
+        }
+        account
+    }
+
+    fn storage_slot(&mut self, address: &Address, index: &U256) -> Option<U256> {
+        let value = self.storage.get(&(*address, *index)).copied();
+        if value.is_some() {
+            self.touch(*address);
+        }
+        value
+    }
+
+    fn cache_account(&mut self, address: Address, info: AccountInfo) {
+        self.accounts.insert(address, info);
+        self.touch(address);
+    }
+
+    fn cache_storage_slot(&mut self, address: Address, index: U256, value: U256) {
+        self.storage.insert((address, index), value);
+        self.touch(address);
+    }
+
+    /// Marks `address` as modified outside of the cache's own read-through
+    /// population, so it is dropped if the innermost open checkpoint is
+    /// reverted.
+    fn mark_touched(&mut self, address: Address) {
+        self.touched_per_checkpoint
+            .last_mut()
+            .expect("always at least one generation")
+            .insert(address);
+    }
+
+    fn invalidate_account(&mut self, address: &Address) {
+        self.accounts.remove(address);
+        self.storage.retain(|(cached, _), _| cached != address);
+        self.recency.remove(address);
+    }
+
+    /// Starts a new checkpoint generation, so entries touched from here on
+    /// can be dropped cheaply if this checkpoint is reverted, without losing
+    /// track of entries touched by checkpoints enclosing it.
+    fn checkpoint(&mut self) {
+        self.touched_per_checkpoint.push(HashSet::new());
+    }
+
+    /// Drops every entry touched since the innermost open checkpoint,
+    /// leaving entries touched by any enclosing checkpoint intact.
+    fn revert_to_checkpoint(&mut self) {
+        let touched = self.touched_per_checkpoint.pop().unwrap_or_default();
+        for address in touched {
+            self.invalidate_account(&address);
+        }
+
+        if self.touched_per_checkpoint.is_empty() {
+            self.touched_per_checkpoint.push(HashSet::new());
+        }
+    }
+
+    fn clear(&mut self) {
+        self.accounts.clear();
+        self.storage.clear();
+        self.recency.clear();
+        self.touched_per_checkpoint = vec![HashSet::new()];
+    }
+}
 
 /// A state consisting of layers.
 #[derive(Clone, Debug)]
@@ -18,6 +313,8 @@ pub struct LayeredState<Layer: Clone> {
     stack: Vec<Layer>,
     /// Snapshots
     snapshots: HashMap<B256, Vec<Layer>>, // naive implementation
+    /// Canonical read-through cache, layered above `stack`.
+    cache: StateCache,
 }
 
 impl<Layer: Clone> LayeredState<Layer> {
@@ -26,6 +323,18 @@ impl<Layer: Clone> LayeredState<Layer> {
         Self {
             stack: vec![layer],
             snapshots: HashMap::new(),
+            cache: StateCache::with_capacity(DEFAULT_CACHE_CAPACITY),
+        }
+    }
+
+    /// Sets the maximum number of addresses kept in the canonical state
+    /// cache, evicting the least-recently-used entries if it shrinks.
+    pub fn set_cache_capacity(&mut self, capacity: usize) {
+        self.cache.capacity = capacity;
+        while self.cache.recency.len() > self.cache.capacity {
+            if let Some(evicted) = self.cache.recency.pop_lru() {
+                self.cache.invalidate_account(&evicted);
+            }
         }
     }
 
@@ -74,6 +383,7 @@ impl<Layer: Clone + Default> Default for LayeredState<Layer> {
         Self {
             stack: vec![Layer::default()],
             snapshots: HashMap::new(),
+            cache: StateCache::with_capacity(DEFAULT_CACHE_CAPACITY),
         }
     }
 }
@@ -81,12 +391,16 @@ impl<Layer: Clone + Default> Default for LayeredState<Layer> {
 /// A layer with information needed for [`Rethnet`].
 #[derive(Clone, Debug, Default)]
 pub struct RethnetLayer {
-    /// Accounts, where the Option signals deletion.
-    accounts: HashMap<Address, Option<RethnetAccount>>,
-    /// Code hash -> Address
-    contracts: HashMap<B256, Bytecode>,
+    /// Accounts, where the Option signals deletion. Wrapped in an `Arc` so
+    /// that cloning a layer (e.g. into a snapshot) is cheap; a layer's
+    /// accounts are only copied, copy-on-write, the first time it is
+    /// mutated while shared.
+    accounts: Arc<HashMap<Address, Option<RethnetAccount>>>,
     /// Cached state root
     state_root: Option<B256>,
+    /// The storage values as of the start of this checkpoint, captured
+    /// lazily the first time a slot is read or written within it.
+    original_storage: HashMap<(Address, U256), U256>,
 }
 
 impl RethnetLayer {
@@ -97,20 +411,16 @@ impl RethnetLayer {
             .map(|(address, account_info)| (address, Some(account_info.into())))
             .collect();
 
-        let contracts = accounts
-            .values_mut()
-            .filter_map(|account| {
-                account.as_mut().and_then(|account| {
-                    let code = account.split_code();
-                    code.map(|code| (code.hash(), code))
-                })
-            })
-            .collect();
+        accounts.values_mut().flatten().for_each(|account| {
+            if let Some(code) = account.split_code() {
+                insert_contract(code);
+            }
+        });
 
         Self {
-            accounts,
-            contracts,
+            accounts: Arc::new(accounts),
             state_root: None,
+            original_storage: HashMap::new(),
         }
     }
 
@@ -128,10 +438,10 @@ impl RethnetLayer {
         if account.info.code_hash == KECCAK_EMPTY {
             account.info.code = Some(Bytecode::new())
         } else if let Some(code) = account.split_code() {
-            self.contracts.insert(code.hash(), code);
+            insert_contract(code);
         }
 
-        self.accounts.insert(address, Some(account));
+        Arc::make_mut(&mut self.accounts).insert(address, Some(account));
     }
 }
 
@@ -147,12 +457,11 @@ impl LayeredState<RethnetLayer> {
     pub fn account_mut(&mut self, address: &Address) -> Option<&mut Option<RethnetAccount>> {
         // WORKAROUND: https://blog.rust-lang.org/2022/08/05/nll-by-default.html
         if self.last_layer_mut().accounts.contains_key(address) {
-            return self.last_layer_mut().accounts.get_mut(address);
+            return Arc::make_mut(&mut self.last_layer_mut().accounts).get_mut(address);
         }
 
         self.account(address).cloned().map(|account| {
-            self.last_layer_mut()
-                .accounts
+            Arc::make_mut(&mut self.last_layer_mut().accounts)
                 .insert_unique_unchecked(*address, Some(account))
                 .1
         })
@@ -171,9 +480,7 @@ impl LayeredState<RethnetLayer> {
                 .is_none();
 
             if !was_deleted {
-                return self
-                    .last_layer_mut()
-                    .accounts
+                return Arc::make_mut(&mut self.last_layer_mut().accounts)
                     .get_mut(address)
                     .unwrap()
                     .as_mut()
@@ -183,31 +490,76 @@ impl LayeredState<RethnetLayer> {
 
         let account = self.account(address).cloned().unwrap_or_default();
 
-        self.last_layer_mut()
-            .accounts
+        Arc::make_mut(&mut self.last_layer_mut().accounts)
             .insert_unique_unchecked(*address, Some(account))
             .1
             .as_mut()
             .unwrap()
     }
 
+    /// Captures the storage slot's baseline value, for EIP-2200/1283 net gas
+    /// metering, unless a baseline is already recorded somewhere in the
+    /// layer stack. Must be called before every write to the slot, as well
+    /// as every read of its original value, so that whichever happens first
+    /// is the one that captures it.
+    ///
+    /// Baselines are looked up across the whole stack, not just the current
+    /// layer: a nested checkpoint (e.g. a sub-call) must see the baseline
+    /// captured by an enclosing one, rather than re-capturing against a
+    /// value the enclosing checkpoint has since mutated.
+    fn capture_original_storage_slot(&mut self, address: Address, index: U256) {
+        let already_captured = self
+            .iter()
+            .any(|layer| layer.original_storage.contains_key(&(address, index)));
+
+        if already_captured {
+            return;
+        }
+
+        let value = self
+            .account(&address)
+            .and_then(|account| account.storage.get(&index))
+            .copied()
+            .unwrap_or(U256::ZERO);
+
+        self.last_layer_mut()
+            .original_storage
+            .insert((address, index), value);
+    }
+
+    /// Returns the storage slot's original value, for EIP-2200/1283 net gas
+    /// metering. The baseline is captured lazily, the first time the slot is
+    /// read through this method or written through
+    /// [`StateDebug::set_account_storage_slot`], and is kept by the layer that
+    /// was on top at that moment. A baseline recorded by any layer still on
+    /// the stack is returned, so a nested checkpoint sees the baseline of an
+    /// enclosing one.
+    pub fn original_storage_slot(
+        &mut self,
+        address: Address,
+        index: U256,
+    ) -> Result<U256, StateError> {
+        self.capture_original_storage_slot(address, index);
+
+        let value = self
+            .iter()
+            .find_map(|layer| layer.original_storage.get(&(address, index)))
+            .copied()
+            .expect("captured above");
+
+        Ok(value)
+    }
+
     /// Removes the [`AccountInfo`] corresponding to the specified address.
     fn remove_account(&mut self, address: &Address) -> Option<AccountInfo> {
         if let Some(account) = self.account(address) {
             let account_info = account.info.clone();
 
-            if account.info.code_hash != KECCAK_EMPTY {
-                debug_assert!(account.info.code.is_none());
-
-                let code_hash = account.info.code_hash;
-
-                self.last_layer_mut()
-                    .contracts
-                    .insert(code_hash, Bytecode::new());
-            }
-
-            // Insert `None` to signal that the account was deleted
-            self.last_layer_mut().accounts.insert(*address, None);
+            // Insert `None` to signal that the account was deleted. The
+            // account's code, if any, is left in the global contract store:
+            // it's content-addressed and immutable, so an unreferenced hash
+            // is simply inert rather than needing to be blanked out.
+            Arc::make_mut(&mut self.last_layer_mut().accounts).insert(*address, None);
 
             return Some(account_info);
         }
@@ -220,26 +572,42 @@ impl State for LayeredState<RethnetLayer> {
     type Error = StateError;
 
     fn basic(&mut self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        if let Some(info) = self.cache.account(&address) {
+            return Ok(Some(info));
+        }
+
         let account = self.account(&address).map(|account| account.info.clone());
 
-        // TODO: Move this out of LayeredState when forking
-        let account = Some(account.unwrap_or_default());
+        if let Some(account) = &account {
+            self.cache.cache_account(address, account.clone());
+        }
 
         Ok(account)
     }
 
     fn code_by_hash(&mut self, code_hash: B256) -> Result<Bytecode, Self::Error> {
-        self.iter()
-            .find_map(|layer| layer.contracts.get(&code_hash).cloned())
+        contracts()
+            .lock()
+            .unwrap()
+            .get(&code_hash)
+            .cloned()
             .ok_or(StateError::InvalidCodeHash(code_hash))
     }
 
     fn storage(&mut self, address: Address, index: U256) -> Result<U256, Self::Error> {
-        Ok(self
+        if let Some(value) = self.cache.storage_slot(&address, &index) {
+            return Ok(value);
+        }
+
+        let value = self
             .account(&address)
             .and_then(|account| account.storage.get(&index))
-            .cloned()
-            .unwrap_or(U256::ZERO))
+            .copied()
+            .unwrap_or(U256::ZERO);
+
+        self.cache.cache_storage_slot(address, index, value);
+
+        Ok(value)
     }
 }
 
@@ -248,22 +616,41 @@ impl DatabaseCommit for LayeredState<RethnetLayer> {
         changes.into_iter().for_each(|(address, account)| {
             if account.is_empty() || account.is_destroyed {
                 self.remove_account(&address);
+                self.cache.invalidate_account(&address);
+                self.cache.mark_touched(address);
             } else {
                 let old_account = self.account_or_insert_mut(&address);
-                old_account.info = account.info;
+                old_account.info = account.info.clone();
 
                 if account.storage_cleared {
                     old_account.storage.clear();
+                    old_account.dirty_storage_root();
                 }
 
-                account.storage.into_iter().for_each(|(index, value)| {
-                    let value = value.present_value();
-                    if value == U256::ZERO {
-                        old_account.storage.remove(&index);
+                let updated_slots: Vec<_> = account
+                    .storage
+                    .into_iter()
+                    .map(|(index, value)| (index, value.present_value()))
+                    .collect();
+
+                updated_slots.iter().for_each(|(index, value)| {
+                    if *value == U256::ZERO {
+                        old_account.storage.remove(index);
                     } else {
-                        old_account.storage.insert(index, value);
+                        old_account.storage.insert(*index, *value);
                     }
                 });
+
+                if !updated_slots.is_empty() {
+                    old_account.dirty_storage_root();
+                }
+
+                self.cache.cache_account(address, account.info);
+                self.cache.mark_touched(address);
+
+                for (index, value) in updated_slots {
+                    self.cache.cache_storage_slot(address, index, value);
+                }
             }
         });
     }
@@ -274,8 +661,9 @@ impl StateDebug for LayeredState<RethnetLayer> {
 
     fn account_storage_root(&mut self, address: &Address) -> Result<Option<B256>, Self::Error> {
         Ok(self
-            .account(address)
-            .map(|account| storage_root(&account.storage)))
+            .account_mut(address)
+            .and_then(Option::as_mut)
+            .map(RethnetAccount::storage_root))
     }
 
     fn insert_account(
@@ -286,6 +674,17 @@ impl StateDebug for LayeredState<RethnetLayer> {
         self.last_layer_mut()
             .insert_account(address, account_info.into());
 
+        // Re-read the account rather than caching `account_info` verbatim:
+        // `RethnetLayer::insert_account` normalizes the code hash and splits
+        // out inline code, so the cache must reflect the same values that
+        // were actually stored. The inserted account's storage starts empty,
+        // so previously cached slots of the address are dropped as well.
+        self.cache.invalidate_account(&address);
+        if let Some(info) = self.account(&address).map(|account| account.info.clone()) {
+            self.cache.cache_account(address, info);
+        }
+        self.cache.mark_touched(address);
+
         Ok(())
     }
 
@@ -312,7 +711,6 @@ impl StateDebug for LayeredState<RethnetLayer> {
         modifier: Box<dyn Fn(&mut U256, &mut u64, &mut Option<Bytecode>) + Send>,
     ) -> Result<(), Self::Error> {
         let account = self.account_or_insert_mut(&address);
-        let old_code_hash = account.info.code_hash;
 
         modifier(
             &mut account.info.balance,
@@ -329,23 +727,26 @@ impl StateDebug for LayeredState<RethnetLayer> {
         account.info.code_hash = new_code_hash;
 
         if new_code_hash != KECCAK_EMPTY {
-            // Store code separately from the account
+            // Store code separately from the account, in the global,
+            // content-addressed contract store.
             let code = account.info.code.take().unwrap();
-            self.last_layer_mut().contracts.insert(new_code_hash, code);
+            insert_contract(code);
         }
 
-        if old_code_hash != KECCAK_EMPTY && old_code_hash != new_code_hash {
-            // The old contract should now return empty bytecode
-            self.last_layer_mut()
-                .contracts
-                .insert(old_code_hash, Bytecode::new());
-        }
+        let info = account.info.clone();
+
+        self.cache.cache_account(address, info);
+        self.cache.mark_touched(address);
 
         Ok(())
     }
 
     fn remove_account(&mut self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
-        Ok(self.remove_account(&address))
+        let account_info = self.remove_account(&address);
+        self.cache.invalidate_account(&address);
+        self.cache.mark_touched(address);
+
+        Ok(account_info)
     }
 
     fn remove_snapshot(&mut self, state_root: &B256) -> bool {
@@ -358,9 +759,14 @@ impl StateDebug for LayeredState<RethnetLayer> {
         index: U256,
         value: U256,
     ) -> Result<(), Self::Error> {
-        self.account_or_insert_mut(&address)
-            .storage
-            .insert(index, value);
+        self.capture_original_storage_slot(address, index);
+
+        let account = self.account_or_insert_mut(&address);
+        account.storage.insert(index, value);
+        account.dirty_storage_root();
+
+        self.cache.cache_storage_slot(address, index, value);
+        self.cache.mark_touched(address);
 
         Ok(())
     }
@@ -374,20 +780,28 @@ impl StateDebug for LayeredState<RethnetLayer> {
 
         if let Some(snapshot) = self.snapshots.remove(state_root) {
             self.stack = snapshot;
+            // The snapshot may have been taken from an arbitrary point in
+            // history, so the cache can no longer be trusted incrementally.
+            self.cache.clear();
 
             return Ok(());
         }
 
-        let layer_id = self.stack.iter().enumerate().find_map(|(layer_id, layer)| {
-            if layer.state_root.unwrap() == *state_root {
-                Some(layer_id)
-            } else {
-                None
+        let mut layer_id = None;
+        for (id, layer) in self.stack.iter().enumerate() {
+            let layer_state_root = layer
+                .state_root
+                .ok_or(StateError::MissingLayerStateRoot(id))?;
+
+            if layer_state_root == *state_root {
+                layer_id = Some(id);
+                break;
             }
-        });
+        }
 
         if let Some(layer_id) = layer_id {
             self.stack.truncate(layer_id + 1);
+            self.cache.clear();
 
             Ok(())
         } else {
@@ -398,18 +812,18 @@ impl StateDebug for LayeredState<RethnetLayer> {
     fn state_root(&mut self) -> Result<B256, Self::Error> {
         let mut state = HashMap::new();
 
-        self.iter()
-            .flat_map(|layer| layer.accounts.iter())
-            .for_each(|(address, account)| {
+        for layer in self.stack.iter_mut().rev() {
+            for (address, account) in Arc::make_mut(&mut layer.accounts).iter_mut() {
                 state
                     .entry(*address)
-                    .or_insert(account.as_ref().map(|account| BasicAccount {
+                    .or_insert(account.as_mut().map(|account| BasicAccount {
                         nonce: U256::from(account.info.nonce),
                         balance: account.info.balance,
-                        storage_root: storage_root(&account.storage),
+                        storage_root: account.storage_root(),
                         code_hash: account.info.code_hash,
                     }));
-            });
+            }
+        }
 
         let state = state
             .iter()
@@ -423,6 +837,7 @@ impl StateDebug for LayeredState<RethnetLayer> {
         self.last_layer_mut().state_root.replace(state_root);
 
         self.add_layer_default();
+        self.cache.checkpoint();
 
         Ok(())
     }
@@ -431,9 +846,53 @@ impl StateDebug for LayeredState<RethnetLayer> {
         let last_layer_id = self.last_layer_id();
         if last_layer_id > 0 {
             self.revert_to_layer(last_layer_id - 1);
+            self.cache.revert_to_checkpoint();
             Ok(())
         } else {
             Err(StateError::CannotRevert)
         }
     }
+
+    fn dump(&self) -> HashMap<Address, AccountDump> {
+        dump_layers(&self.stack)
+    }
+
+    fn diff_against_snapshot(&self, state_root: &B256) -> StateDiff {
+        let current = self.dump();
+
+        let Some(previous_stack) = self.snapshots.get(state_root) else {
+            return current
+                .into_iter()
+                .map(|(address, account)| (address, AccountChange::Created(account)))
+                .collect();
+        };
+
+        let previous = dump_layers(previous_stack);
+
+        let mut diff: StateDiff = previous
+            .iter()
+            .filter(|(address, _)| !current.contains_key(address))
+            .map(|(address, account)| (*address, AccountChange::Deleted(account.clone())))
+            .collect();
+
+        for (address, after) in current {
+            match previous.get(&address) {
+                None => {
+                    diff.insert(address, AccountChange::Created(after));
+                }
+                Some(before) => {
+                    let change = diff_accounts(before, &after);
+                    if !matches!(
+                        &change,
+                        AccountChange::Changed { balance: None, nonce: None, code: None, storage }
+                            if storage.is_empty()
+                    ) {
+                        diff.insert(address, change);
+                    }
+                }
+            }
+        }
+
+        diff
+    }
 }
@@ -0,0 +1,120 @@
+mod account;
+mod layered_state;
+
+use hashbrown::HashMap;
+use rethnet_eth::{Address, B256, U256};
+use revm::primitives::{AccountInfo, Bytecode};
+
+pub use account::AccountDump;
+pub use layered_state::{LayeredState, RethnetLayer};
+
+/// A value that differs between two states.
+#[derive(Clone, Debug)]
+pub struct Change<T> {
+    /// The value before.
+    pub before: T,
+    /// The value after.
+    pub after: T,
+}
+
+/// How a single account changed between two state roots.
+#[derive(Clone, Debug)]
+pub enum AccountChange {
+    /// The account didn't exist before, but exists now.
+    Created(AccountDump),
+    /// The account existed before, but was deleted.
+    Deleted(AccountDump),
+    /// The account existed in both states, with the given deltas.
+    Changed {
+        /// The balance, if it changed.
+        balance: Option<Change<U256>>,
+        /// The nonce, if it changed.
+        nonce: Option<Change<u64>>,
+        /// The code, if it changed.
+        code: Option<Change<Bytecode>>,
+        /// Storage slots whose value changed, keyed by slot index.
+        storage: HashMap<U256, Change<U256>>,
+    },
+}
+
+/// The difference between two states, keyed by address.
+pub type StateDiff = HashMap<Address, AccountChange>;
+
+/// Error type for a [`StateDebug`] implementation.
+#[derive(Debug, thiserror::Error)]
+pub enum StateError {
+    /// Contract with specified code hash does not exist
+    #[error("Contract with code hash `{0}` does not exist")]
+    InvalidCodeHash(B256),
+    /// No checkpoints to revert
+    #[error("Cannot revert beyond the initial state")]
+    CannotRevert,
+    /// Specified state root does not exist
+    #[error("Unknown state root: `{0}`")]
+    InvalidStateRoot(B256),
+    /// A layer in the stack is missing its cached state root, indicating the
+    /// backing store is corrupted rather than the state genuinely lacking it
+    #[error("Layer {0} is missing its cached state root")]
+    MissingLayerStateRoot(usize),
+}
+
+/// A trait for debug-level operations on state.
+pub trait StateDebug {
+    /// The error type returned by the methods of this trait.
+    type Error;
+
+    /// Retrieves the storage root of the account at the specified address, if it exists.
+    fn account_storage_root(&mut self, address: &Address) -> Result<Option<B256>, Self::Error>;
+
+    /// Inserts the provided `AccountInfo` at the specified `address`.
+    fn insert_account(
+        &mut self,
+        address: Address,
+        account_info: AccountInfo,
+    ) -> Result<(), Self::Error>;
+
+    /// Creates a snapshot of the state, returning its state root and whether it already existed.
+    fn make_snapshot(&mut self) -> (B256, bool);
+
+    /// Modifies the account at the specified address using the provided function.
+    fn modify_account(
+        &mut self,
+        address: Address,
+        modifier: Box<dyn Fn(&mut U256, &mut u64, &mut Option<Bytecode>) + Send>,
+    ) -> Result<(), Self::Error>;
+
+    /// Removes the account at the specified address, if it exists.
+    fn remove_account(&mut self, address: Address) -> Result<Option<AccountInfo>, Self::Error>;
+
+    /// Removes the snapshot corresponding to the specified state root, if it exists.
+    fn remove_snapshot(&mut self, state_root: &B256) -> bool;
+
+    /// Sets the storage slot at the specified address and index to the provided value.
+    fn set_account_storage_slot(
+        &mut self,
+        address: Address,
+        index: U256,
+        value: U256,
+    ) -> Result<(), Self::Error>;
+
+    /// Sets the state to the state corresponding to the specified state root.
+    fn set_state_root(&mut self, state_root: &B256) -> Result<(), Self::Error>;
+
+    /// Retrieves the state root of the state.
+    fn state_root(&mut self) -> Result<B256, Self::Error>;
+
+    /// Creates a checkpoint that can be reverted to using [`StateDebug::revert`].
+    fn checkpoint(&mut self) -> Result<(), Self::Error>;
+
+    /// Reverts to the previous checkpoint.
+    fn revert(&mut self) -> Result<(), Self::Error>;
+
+    /// Materializes the complete state as an address -> account dump map,
+    /// with each account's storage fully expanded.
+    fn dump(&self) -> HashMap<Address, AccountDump>;
+
+    /// Diffs the current state against the state as of the specified state
+    /// root. If no snapshot for `state_root` is known, every live account is
+    /// reported as newly created.
+    fn diff_against_snapshot(&self, state_root: &B256) -> StateDiff;
+}